@@ -1,5 +1,8 @@
 #![allow(dead_code, unused_variables)]
 
+use std::collections::HashMap;
+use std::fmt;
+
 /// We start solving a very important problem:
 ///
 ///     Designing a language with only 3-letter types
@@ -15,91 +18,975 @@ enum Val {
 
 #[derive(Clone, Debug)]
 enum Exp {
-    Var(Val),
+    Lit(Val),
+    Var(String),
+    Let(String, Box<Exp>, Box<Exp>),
     Add(Box<Exp>, Box<Exp>),
     Sub(Box<Exp>, Box<Exp>),
     Mul(Box<Exp>, Box<Exp>),
     Div(Box<Exp>, Box<Exp>),
+    Lt(Box<Exp>, Box<Exp>),
+    Lte(Box<Exp>, Box<Exp>),
+    Gt(Box<Exp>, Box<Exp>),
+    Gte(Box<Exp>, Box<Exp>),
+    Eq(Box<Exp>, Box<Exp>),
+    Ne(Box<Exp>, Box<Exp>),
+    And(Box<Exp>, Box<Exp>),
+    Or(Box<Exp>, Box<Exp>),
+    Not(Box<Exp>),
+    If(Box<Exp>, Box<Exp>, Box<Exp>),
+}
+
+// The chain of scopes visible to an expression being evaluated. `Let`
+// pushes a fresh scope for its body and pops it back off on the way out,
+// so a binding never leaks past the expression that introduced it.
+#[derive(Clone, Debug, Default)]
+struct Env {
+    scopes: Vec<HashMap<String, Val>>,
+}
+
+impl Env {
+    fn new() -> Self {
+        Env {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: String, val: Val) {
+        self.scopes
+            .last_mut()
+            .expect("Env always has at least one scope")
+            .insert(name, val);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Val> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+// Everything that can go wrong while evaluating an `Exp`
+#[derive(Clone, Debug, PartialEq)]
+enum EvalError {
+    InvalidType { expected: &'static str, got: Val },
+    IncomparableTypes { left: Val, right: Val },
+    DivideByZero,
+    ArithmeticOverflow,
+    UndefinedVariable(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::InvalidType { expected, got } => {
+                write!(f, "invalid type: expected {expected}, got {got:?}")
+            }
+            EvalError::IncomparableTypes { left, right } => {
+                write!(f, "incomparable types: {left:?} and {right:?}")
+            }
+            EvalError::DivideByZero => write!(f, "divide by zero"),
+            EvalError::ArithmeticOverflow => write!(f, "arithmetic overflow"),
+            EvalError::UndefinedVariable(name) => write!(f, "undefined variable: {name}"),
+        }
+    }
 }
 
-// Type-checked and error-handled `Var` expression
-fn type_checked_var(var: Val) -> Option<Val> {
-    Some(var)
+impl std::error::Error for EvalError {}
+
+// Type-checked and error-handled `Lit` expression
+fn type_checked_lit(val: Val) -> Result<Val, EvalError> {
+    Ok(val)
+}
+
+// An operand that is neither an `Int` nor a `Rat` is what every arithmetic
+// helper below rejects; this pins down which one and reports it.
+fn expect_numeric(val1: &Val, val2: &Val) -> Result<(), EvalError> {
+    for val in [val1, val2] {
+        if !matches!(val, Val::Int(_) | Val::Rat(_)) {
+            return Err(EvalError::InvalidType {
+                expected: "Int or Rat",
+                got: val.clone(),
+            });
+        }
+    }
+    Ok(())
 }
 
 // Type-checked and error-handled `Add` expression
-fn type_checked_add(val1: Val, val2: Val) -> Option<Val> {
+fn type_checked_add(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    expect_numeric(&val1, &val2)?;
     match (val1, val2) {
-        (Val::Int(val1), Val::Int(val2)) => Some(Val::Int(val1 + val2)),
-        (Val::Int(val1), Val::Rat(val2)) => Some(Val::Rat(val1 as f64 + val2)),
-        (Val::Rat(val1), Val::Int(val2)) => Some(Val::Rat(val1 + val2 as f64)),
-        (Val::Rat(val1), Val::Rat(val2)) => Some(Val::Rat(val1 + val2)),
-        _ => None,
+        (Val::Int(val1), Val::Int(val2)) => val1
+            .checked_add(val2)
+            .map(Val::Int)
+            .ok_or(EvalError::ArithmeticOverflow),
+        (Val::Int(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 as f64 + val2)),
+        (Val::Rat(val1), Val::Int(val2)) => Ok(Val::Rat(val1 + val2 as f64)),
+        (Val::Rat(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 + val2)),
+        _ => unreachable!("expect_numeric already rejected non-numeric operands"),
     }
 }
 
 // Type-checked and error-handled `Sub` expression
-fn type_checked_sub(val1: Val, val2: Val) -> Option<Val> {
+fn type_checked_sub(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    expect_numeric(&val1, &val2)?;
     match (val1, val2) {
-        (Val::Int(val1), Val::Int(val2)) => Some(Val::Int(val1 - val2)),
-        (Val::Int(val1), Val::Rat(val2)) => Some(Val::Rat(val1 as f64 - val2)),
-        (Val::Rat(val1), Val::Int(val2)) => Some(Val::Rat(val1 - val2 as f64)),
-        (Val::Rat(val1), Val::Rat(val2)) => Some(Val::Rat(val1 - val2)),
-        _ => None,
+        (Val::Int(val1), Val::Int(val2)) => val1
+            .checked_sub(val2)
+            .map(Val::Int)
+            .ok_or(EvalError::ArithmeticOverflow),
+        (Val::Int(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 as f64 - val2)),
+        (Val::Rat(val1), Val::Int(val2)) => Ok(Val::Rat(val1 - val2 as f64)),
+        (Val::Rat(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 - val2)),
+        _ => unreachable!("expect_numeric already rejected non-numeric operands"),
     }
 }
 
 // Type-checked and error-handled `Mul` expression
-fn type_checked_mul(val1: Val, val2: Val) -> Option<Val> {
+fn type_checked_mul(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    expect_numeric(&val1, &val2)?;
     match (val1, val2) {
-        (Val::Int(val1), Val::Int(val2)) => Some(Val::Int(val1 * val2)),
-        (Val::Int(val1), Val::Rat(val2)) => Some(Val::Rat(val1 as f64 * val2)),
-        (Val::Rat(val1), Val::Int(val2)) => Some(Val::Rat(val1 * val2 as f64)),
-        (Val::Rat(val1), Val::Rat(val2)) => Some(Val::Rat(val1 * val2)),
-        _ => None,
+        (Val::Int(val1), Val::Int(val2)) => val1
+            .checked_mul(val2)
+            .map(Val::Int)
+            .ok_or(EvalError::ArithmeticOverflow),
+        (Val::Int(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 as f64 * val2)),
+        (Val::Rat(val1), Val::Int(val2)) => Ok(Val::Rat(val1 * val2 as f64)),
+        (Val::Rat(val1), Val::Rat(val2)) => Ok(Val::Rat(val1 * val2)),
+        _ => unreachable!("expect_numeric already rejected non-numeric operands"),
+    }
+}
+
+// `Rat` division never panics, but it can produce an infinite or NaN
+// result; that is this language's form of divide-by-zero.
+fn finite_rat(val: f64) -> Result<Val, EvalError> {
+    if val.is_finite() {
+        Ok(Val::Rat(val))
+    } else {
+        Err(EvalError::DivideByZero)
     }
 }
 
 // Type-checked and error-handled `Div` expression
-fn type_checked_div(val1: Val, val2: Val) -> Option<Val> {
+fn type_checked_div(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    expect_numeric(&val1, &val2)?;
+    match (val1, val2) {
+        (Val::Int(val1), Val::Int(val2)) => val1
+            .checked_div(val2)
+            .map(Val::Int)
+            .ok_or(EvalError::DivideByZero),
+        (Val::Int(val1), Val::Rat(val2)) => finite_rat(val1 as f64 / val2),
+        (Val::Rat(val1), Val::Int(val2)) => finite_rat(val1 / val2 as f64),
+        (Val::Rat(val1), Val::Rat(val2)) => finite_rat(val1 / val2),
+        _ => unreachable!("expect_numeric already rejected non-numeric operands"),
+    }
+}
+
+// Orders two comparable operands: `Int`/`Rat` (promoting `Int` to `f64`
+// when mixed, mirroring the arithmetic helpers above), `Txt`
+// lexicographically, and `Boo` by value. Any other pairing is a type error.
+fn compare_vals(val1: &Val, val2: &Val) -> Result<std::cmp::Ordering, EvalError> {
     match (val1, val2) {
-        (Val::Int(val1), Val::Int(val2)) => Some(Val::Int(val1 / val2)),
-        (Val::Int(val1), Val::Rat(val2)) => Some(Val::Rat(val1 as f64 / val2)),
-        (Val::Rat(val1), Val::Int(val2)) => Some(Val::Rat(val1 / val2 as f64)),
-        (Val::Rat(val1), Val::Rat(val2)) => Some(Val::Rat(val1 / val2)),
-        _ => None,
+        (Val::Int(val1), Val::Int(val2)) => Ok(val1.cmp(val2)),
+        (Val::Int(val1), Val::Rat(val2)) => Ok((*val1 as f64).total_cmp(val2)),
+        (Val::Rat(val1), Val::Int(val2)) => Ok(val1.total_cmp(&(*val2 as f64))),
+        (Val::Rat(val1), Val::Rat(val2)) => Ok(val1.total_cmp(val2)),
+        (Val::Txt(val1), Val::Txt(val2)) => Ok(val1.cmp(val2)),
+        (Val::Boo(val1), Val::Boo(val2)) => Ok(val1.cmp(val2)),
+        (val1, val2) => {
+            for val in [val1, val2] {
+                if !matches!(val, Val::Int(_) | Val::Rat(_) | Val::Txt(_) | Val::Boo(_)) {
+                    return Err(EvalError::InvalidType {
+                        expected: "comparable operands (Int/Rat, Txt, or Boo)",
+                        got: val.clone(),
+                    });
+                }
+            }
+            Err(EvalError::IncomparableTypes {
+                left: val1.clone(),
+                right: val2.clone(),
+            })
+        }
+    }
+}
+
+// Type-checked and error-handled `Lt` expression
+fn type_checked_lt(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_lt()))
+}
+
+// Type-checked and error-handled `Lte` expression
+fn type_checked_lte(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_le()))
+}
+
+// Type-checked and error-handled `Gt` expression
+fn type_checked_gt(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_gt()))
+}
+
+// Type-checked and error-handled `Gte` expression
+fn type_checked_gte(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_ge()))
+}
+
+// Type-checked and error-handled `Eq` expression
+fn type_checked_eq(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_eq()))
+}
+
+// Type-checked and error-handled `Ne` expression
+fn type_checked_ne(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(compare_vals(&val1, &val2)?.is_ne()))
+}
+
+// A non-`Boo` operand is what every logical helper below rejects.
+fn expect_boolean(val: &Val) -> Result<bool, EvalError> {
+    match val {
+        Val::Boo(val) => Ok(*val),
+        val => Err(EvalError::InvalidType {
+            expected: "Boo",
+            got: val.clone(),
+        }),
     }
 }
 
+// Type-checked and error-handled `And` expression
+fn type_checked_and(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    let (val1, val2) = (expect_boolean(&val1)?, expect_boolean(&val2)?);
+    Ok(Val::Boo(val1 && val2))
+}
+
+// Type-checked and error-handled `Or` expression
+fn type_checked_or(val1: Val, val2: Val) -> Result<Val, EvalError> {
+    let (val1, val2) = (expect_boolean(&val1)?, expect_boolean(&val2)?);
+    Ok(Val::Boo(val1 || val2))
+}
+
+// Type-checked and error-handled `Not` expression
+fn type_checked_not(val: Val) -> Result<Val, EvalError> {
+    Ok(Val::Boo(!expect_boolean(&val)?))
+}
+
 // Interpreter
-fn eval(expr: Exp) -> Option<Val> {
+fn eval(expr: Exp, env: &mut Env) -> Result<Val, EvalError> {
     match expr {
-        Exp::Var(var) => type_checked_var(var),
-        Exp::Add(exp1, exp2) => type_checked_add(eval(*exp1)?, eval(*exp2)?),
-        Exp::Sub(exp1, exp2) => type_checked_sub(eval(*exp1)?, eval(*exp2)?),
-        Exp::Mul(exp1, exp2) => type_checked_mul(eval(*exp1)?, eval(*exp2)?),
-        Exp::Div(exp1, exp2) => type_checked_div(eval(*exp1)?, eval(*exp2)?),
+        Exp::Lit(val) => type_checked_lit(val),
+        Exp::Var(name) => env
+            .lookup(&name)
+            .cloned()
+            .ok_or(EvalError::UndefinedVariable(name)),
+        Exp::Let(name, bound, body) => {
+            let val = eval(*bound, env)?;
+            env.push_scope();
+            env.bind(name, val);
+            let result = eval(*body, env);
+            env.pop_scope();
+            result
+        }
+        Exp::Add(exp1, exp2) => type_checked_add(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Sub(exp1, exp2) => type_checked_sub(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Mul(exp1, exp2) => type_checked_mul(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Div(exp1, exp2) => type_checked_div(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Lt(exp1, exp2) => type_checked_lt(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Lte(exp1, exp2) => type_checked_lte(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Gt(exp1, exp2) => type_checked_gt(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Gte(exp1, exp2) => type_checked_gte(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Eq(exp1, exp2) => type_checked_eq(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Ne(exp1, exp2) => type_checked_ne(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::And(exp1, exp2) => type_checked_and(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Or(exp1, exp2) => type_checked_or(eval(*exp1, env)?, eval(*exp2, env)?),
+        Exp::Not(exp) => type_checked_not(eval(*exp, env)?),
+        Exp::If(cond, then_branch, else_branch) => {
+            if expect_boolean(&eval(*cond, env)?)? {
+                eval(*then_branch, env)
+            } else {
+                eval(*else_branch, env)
+            }
+        }
+    }
+}
+
+// A lexical token; `lex` pairs each one with the character offset in the
+// source where it starts (used to locate errors).
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Int(u64),
+    Rat(f64),
+    Txt(String),
+    Boo(bool),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Eq,
+    EqEq,
+    Ne,
+    And,
+    Or,
+    Not,
+    Let,
+    In,
+    If,
+    Then,
+    Else,
+    LParen,
+    RParen,
+    Eof,
+}
+
+// A lexical or syntactic error, tagged with the character offset in the
+// source where it was detected.
+#[derive(Clone, Debug, PartialEq)]
+struct ParseError {
+    message: String,
+    position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error at {}: {}", self.position, self.message)
     }
 }
 
+impl std::error::Error for ParseError {}
+
+// Tokenizer: turns source text into a flat stream of `Token`s, each
+// carrying the character offset it started at.
+fn lex(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        let start = i;
+
+        if ch.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match ch {
+            '+' => {
+                tokens.push((Token::Plus, start));
+                i += 1;
+            }
+            '-' => {
+                tokens.push((Token::Minus, start));
+                i += 1;
+            }
+            '*' => {
+                tokens.push((Token::Star, start));
+                i += 1;
+            }
+            '/' => {
+                tokens.push((Token::Slash, start));
+                i += 1;
+            }
+            '(' => {
+                tokens.push((Token::LParen, start));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, start));
+                i += 1;
+            }
+            '<' => {
+                i += 1;
+                if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    tokens.push((Token::Lte, start));
+                } else {
+                    tokens.push((Token::Lt, start));
+                }
+            }
+            '>' => {
+                i += 1;
+                if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    tokens.push((Token::Gte, start));
+                } else {
+                    tokens.push((Token::Gt, start));
+                }
+            }
+            '=' => {
+                i += 1;
+                if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    tokens.push((Token::EqEq, start));
+                } else {
+                    tokens.push((Token::Eq, start));
+                }
+            }
+            '!' => {
+                i += 1;
+                if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    tokens.push((Token::Ne, start));
+                } else {
+                    return Err(ParseError {
+                        message: "expected '!=', found a bare '!'".to_string(),
+                        position: start,
+                    });
+                }
+            }
+            '"' => {
+                i += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.get(i) {
+                        Some('"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&c) => {
+                            text.push(c);
+                            i += 1;
+                        }
+                        None => {
+                            return Err(ParseError {
+                                message: "unterminated string literal".to_string(),
+                                position: start,
+                            })
+                        }
+                    }
+                }
+                tokens.push((Token::Txt(text), start));
+            }
+            c if c.is_ascii_digit() => {
+                let mut text = String::new();
+                while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                let mut is_rat = false;
+                if chars.get(i) == Some(&'.')
+                    && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+                {
+                    is_rat = true;
+                    text.push('.');
+                    i += 1;
+                    while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                        text.push(chars[i]);
+                        i += 1;
+                    }
+                }
+                if is_rat {
+                    let val: f64 = text.parse().map_err(|_| ParseError {
+                        message: format!("invalid number literal `{text}`"),
+                        position: start,
+                    })?;
+                    tokens.push((Token::Rat(val), start));
+                } else {
+                    let val: u64 = text.parse().map_err(|_| ParseError {
+                        message: format!("invalid number literal `{text}`"),
+                        position: start,
+                    })?;
+                    tokens.push((Token::Int(val), start));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut text = String::new();
+                while chars
+                    .get(i)
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                let token = match text.as_str() {
+                    "true" => Token::Boo(true),
+                    "false" => Token::Boo(false),
+                    "let" => Token::Let,
+                    "in" => Token::In,
+                    "if" => Token::If,
+                    "then" => Token::Then,
+                    "else" => Token::Else,
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(text),
+                };
+                tokens.push((token, start));
+            }
+            _ => {
+                return Err(ParseError {
+                    message: format!("unexpected character '{ch}'"),
+                    position: start,
+                })
+            }
+        }
+    }
+
+    tokens.push((Token::Eof, chars.len()));
+    Ok(tokens)
+}
+
+// Recursive-descent / precedence-climbing parser. Precedence from loosest
+// to tightest: `let`/`if`, `or`, `and`, `not`, comparisons, `+`/`-`,
+// `*`/`/`, primaries. `+`/`-`, `*`/`/`, `and` and `or` are left-associative;
+// comparisons do not chain (`1 < 2 < 3` is a parse error).
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, usize)>) -> Self {
+        Parser { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_pos(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), ParseError> {
+        if self.peek() == expected {
+            self.advance();
+            Ok(())
+        } else {
+            Err(ParseError {
+                message: format!("expected {expected:?}, found {:?}", self.peek()),
+                position: self.peek_pos(),
+            })
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Exp, ParseError> {
+        match self.peek() {
+            Token::Let => self.parse_let(),
+            Token::If => self.parse_if(),
+            _ => self.parse_or(),
+        }
+    }
+
+    fn parse_let(&mut self) -> Result<Exp, ParseError> {
+        let pos = self.peek_pos();
+        self.expect(&Token::Let)?;
+        let name = match self.advance() {
+            Token::Ident(name) => name,
+            other => {
+                return Err(ParseError {
+                    message: format!("expected identifier after `let`, found {other:?}"),
+                    position: pos,
+                })
+            }
+        };
+        self.expect(&Token::Eq)?;
+        let bound = self.parse_expr()?;
+        self.expect(&Token::In)?;
+        let body = self.parse_expr()?;
+        Ok(Exp::Let(name, Box::new(bound), Box::new(body)))
+    }
+
+    fn parse_if(&mut self) -> Result<Exp, ParseError> {
+        self.expect(&Token::If)?;
+        let cond = self.parse_expr()?;
+        self.expect(&Token::Then)?;
+        let then_branch = self.parse_expr()?;
+        self.expect(&Token::Else)?;
+        let else_branch = self.parse_expr()?;
+        Ok(Exp::If(
+            Box::new(cond),
+            Box::new(then_branch),
+            Box::new(else_branch),
+        ))
+    }
+
+    fn parse_or(&mut self) -> Result<Exp, ParseError> {
+        let mut left = self.parse_and()?;
+        while *self.peek() == Token::Or {
+            self.advance();
+            let right = self.parse_and()?;
+            left = Exp::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Exp, ParseError> {
+        let mut left = self.parse_not()?;
+        while *self.peek() == Token::And {
+            self.advance();
+            let right = self.parse_not()?;
+            left = Exp::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Exp, ParseError> {
+        if *self.peek() == Token::Not {
+            self.advance();
+            let operand = self.parse_not()?;
+            Ok(Exp::Not(Box::new(operand)))
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Exp, ParseError> {
+        let left = self.parse_additive()?;
+        let ctor: fn(Box<Exp>, Box<Exp>) -> Exp = match self.peek() {
+            Token::Lt => Exp::Lt,
+            Token::Lte => Exp::Lte,
+            Token::Gt => Exp::Gt,
+            Token::Gte => Exp::Gte,
+            Token::EqEq => Exp::Eq,
+            Token::Ne => Exp::Ne,
+            _ => return Ok(left),
+        };
+        self.advance();
+        let right = self.parse_additive()?;
+        Ok(ctor(Box::new(left), Box::new(right)))
+    }
+
+    fn parse_additive(&mut self) -> Result<Exp, ParseError> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let ctor: fn(Box<Exp>, Box<Exp>) -> Exp = match self.peek() {
+                Token::Plus => Exp::Add,
+                Token::Minus => Exp::Sub,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_multiplicative()?;
+            left = ctor(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Exp, ParseError> {
+        let mut left = self.parse_primary()?;
+        loop {
+            let ctor: fn(Box<Exp>, Box<Exp>) -> Exp = match self.peek() {
+                Token::Star => Exp::Mul,
+                Token::Slash => Exp::Div,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_primary()?;
+            left = ctor(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_primary(&mut self) -> Result<Exp, ParseError> {
+        let pos = self.peek_pos();
+        match self.advance() {
+            Token::Int(val) => Ok(Exp::Lit(Val::Int(val))),
+            Token::Rat(val) => Ok(Exp::Lit(Val::Rat(val))),
+            Token::Txt(val) => Ok(Exp::Lit(Val::Txt(val))),
+            Token::Boo(val) => Ok(Exp::Lit(Val::Boo(val))),
+            Token::Ident(name) => Ok(Exp::Var(name)),
+            Token::LParen => {
+                let inner = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(inner)
+            }
+            other => Err(ParseError {
+                message: format!("unexpected token {other:?}"),
+                position: pos,
+            }),
+        }
+    }
+}
+
+// Front end: lex and parse a full expression, rejecting any trailing input.
+fn parse(source: &str) -> Result<Exp, ParseError> {
+    let tokens = lex(source)?;
+    let mut parser = Parser::new(tokens);
+    let expr = parser.parse_expr()?;
+    parser.expect(&Token::Eof)?;
+    Ok(expr)
+}
+
+// A type in the `ichigo` type system, or `Var` for a not-yet-resolved
+// type variable produced during inference.
+#[derive(Clone, Debug, PartialEq)]
+enum Ty {
+    Int,
+    Rat,
+    Bool,
+    Txt,
+    Nil,
+    Var(usize),
+}
+
+// Everything that can go wrong while type-checking an `Exp`
+#[derive(Clone, Debug, PartialEq)]
+enum TypeError {
+    Mismatch { expected: Ty, got: Ty },
+    UndefinedVariable(String),
+    InfiniteType { var: usize, ty: Ty },
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::Mismatch { expected, got } => {
+                write!(f, "type mismatch: expected {expected:?}, got {got:?}")
+            }
+            TypeError::UndefinedVariable(name) => write!(f, "undefined variable: {name}"),
+            TypeError::InfiniteType { var, ty } => {
+                write!(f, "infinite type: ?{var} occurs in {ty:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+// The chain of scopes visible to an expression being type-checked, mirroring
+// `Env` but carrying types instead of values.
+#[derive(Clone, Debug, Default)]
+struct TyEnv {
+    scopes: Vec<HashMap<String, Ty>>,
+}
+
+impl TyEnv {
+    fn new() -> Self {
+        TyEnv {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: String, ty: Ty) {
+        self.scopes
+            .last_mut()
+            .expect("TyEnv always has at least one scope")
+            .insert(name, ty);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Ty> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+// A union-find substitution map from type variables to types, plus the
+// monotonic counter that hands out fresh variables.
+#[derive(Default)]
+struct Subst {
+    next_var: usize,
+    bindings: HashMap<usize, Ty>,
+}
+
+impl Subst {
+    fn fresh(&mut self) -> Ty {
+        let var = self.next_var;
+        self.next_var += 1;
+        Ty::Var(var)
+    }
+
+    // Follows the substitution chain until it reaches a concrete type or
+    // an unbound variable.
+    fn resolve(&self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(var) => match self.bindings.get(var) {
+                Some(bound) => self.resolve(bound),
+                None => ty.clone(),
+            },
+            other => other.clone(),
+        }
+    }
+
+    fn occurs(&self, var: usize, ty: &Ty) -> bool {
+        matches!(self.resolve(ty), Ty::Var(other) if other == var)
+    }
+
+    fn bind(&mut self, var: usize, ty: Ty) -> Result<(), TypeError> {
+        if self.occurs(var, &ty) {
+            return Err(TypeError::InfiniteType { var, ty });
+        }
+        self.bindings.insert(var, ty);
+        Ok(())
+    }
+
+    // Unifies two types, binding whichever side is an unresolved variable.
+    fn unify(&mut self, ty1: &Ty, ty2: &Ty) -> Result<Ty, TypeError> {
+        match (self.resolve(ty1), self.resolve(ty2)) {
+            (Ty::Var(var1), Ty::Var(var2)) if var1 == var2 => Ok(Ty::Var(var1)),
+            (Ty::Var(var), ty) | (ty, Ty::Var(var)) => {
+                self.bind(var, ty.clone())?;
+                Ok(ty)
+            }
+            (ty1, ty2) if ty1 == ty2 => Ok(ty1),
+            (expected, got) => Err(TypeError::Mismatch { expected, got }),
+        }
+    }
+
+    // Unifies two operands that must both be numeric, promoting to `Rat`
+    // when either side is `Rat` (an unresolved variable defaults to `Int`).
+    fn unify_numeric(&mut self, ty1: &Ty, ty2: &Ty) -> Result<Ty, TypeError> {
+        match (self.resolve(ty1), self.resolve(ty2)) {
+            (Ty::Rat, Ty::Rat) | (Ty::Rat, Ty::Int) | (Ty::Int, Ty::Rat) => Ok(Ty::Rat),
+            (Ty::Int, Ty::Int) => Ok(Ty::Int),
+            (Ty::Var(var), numeric @ (Ty::Int | Ty::Rat))
+            | (numeric @ (Ty::Int | Ty::Rat), Ty::Var(var)) => {
+                self.bind(var, numeric.clone())?;
+                Ok(numeric)
+            }
+            (Ty::Var(var1), Ty::Var(var2)) => {
+                self.bind(var1, Ty::Int)?;
+                self.bind(var2, Ty::Int)?;
+                Ok(Ty::Int)
+            }
+            (ty1, ty2) => {
+                let (numeric, other) = if matches!(ty1, Ty::Int | Ty::Rat) {
+                    (ty1, ty2)
+                } else if matches!(ty2, Ty::Int | Ty::Rat) {
+                    (ty2, ty1)
+                } else {
+                    (Ty::Int, ty1)
+                };
+                Err(TypeError::Mismatch {
+                    expected: numeric,
+                    got: other,
+                })
+            }
+        }
+    }
+
+    /// Unifies two types, promoting Int/Rat the way numeric operands do,
+    /// but falling back to exact unification for everything else.
+    fn unify_promoting(&mut self, ty1: &Ty, ty2: &Ty) -> Result<Ty, TypeError> {
+        match (self.resolve(ty1), self.resolve(ty2)) {
+            (Ty::Int | Ty::Rat, Ty::Int | Ty::Rat) => self.unify_numeric(ty1, ty2),
+            _ => self.unify(ty1, ty2),
+        }
+    }
+}
+
+fn lit_ty(val: &Val) -> Ty {
+    match val {
+        Val::Boo(_) => Ty::Bool,
+        Val::Int(_) => Ty::Int,
+        Val::Nil => Ty::Nil,
+        Val::Rat(_) => Ty::Rat,
+        Val::Txt(_) => Ty::Txt,
+    }
+}
+
+// Assigns `expr` a fresh type variable, unifies it with the type implied by
+// its shape, and returns the variable resolved through `subst`.
+fn infer(expr: &Exp, env: &mut TyEnv, subst: &mut Subst) -> Result<Ty, TypeError> {
+    let var = subst.fresh();
+    let ty = match expr {
+        Exp::Lit(val) => lit_ty(val),
+        Exp::Var(name) => env
+            .lookup(name)
+            .cloned()
+            .ok_or_else(|| TypeError::UndefinedVariable(name.clone()))?,
+        Exp::Let(name, bound, body) => {
+            let bound_ty = infer(bound, env, subst)?;
+            env.push_scope();
+            env.bind(name.clone(), bound_ty);
+            let body_ty = infer(body, env, subst);
+            env.pop_scope();
+            body_ty?
+        }
+        Exp::Add(lhs, rhs) | Exp::Sub(lhs, rhs) | Exp::Mul(lhs, rhs) | Exp::Div(lhs, rhs) => {
+            let lhs_ty = infer(lhs, env, subst)?;
+            let rhs_ty = infer(rhs, env, subst)?;
+            subst.unify_numeric(&lhs_ty, &rhs_ty)?
+        }
+        Exp::Lt(lhs, rhs)
+        | Exp::Lte(lhs, rhs)
+        | Exp::Gt(lhs, rhs)
+        | Exp::Gte(lhs, rhs)
+        | Exp::Eq(lhs, rhs)
+        | Exp::Ne(lhs, rhs) => {
+            let lhs_ty = infer(lhs, env, subst)?;
+            let rhs_ty = infer(rhs, env, subst)?;
+            subst.unify_promoting(&lhs_ty, &rhs_ty)?;
+            Ty::Bool
+        }
+        Exp::And(lhs, rhs) | Exp::Or(lhs, rhs) => {
+            let lhs_ty = infer(lhs, env, subst)?;
+            let rhs_ty = infer(rhs, env, subst)?;
+            subst.unify(&Ty::Bool, &lhs_ty)?;
+            subst.unify(&Ty::Bool, &rhs_ty)?;
+            Ty::Bool
+        }
+        Exp::Not(operand) => {
+            let operand_ty = infer(operand, env, subst)?;
+            subst.unify(&Ty::Bool, &operand_ty)?;
+            Ty::Bool
+        }
+        Exp::If(cond, then_branch, else_branch) => {
+            let cond_ty = infer(cond, env, subst)?;
+            subst.unify(&Ty::Bool, &cond_ty)?;
+            let then_ty = infer(then_branch, env, subst)?;
+            let else_ty = infer(else_branch, env, subst)?;
+            subst.unify_promoting(&then_ty, &else_ty)?
+        }
+    };
+    subst.unify(&var, &ty)?;
+    Ok(subst.resolve(&var))
+}
+
+// Static type-checking pass: infers `expr`'s type without evaluating it,
+// catching type errors that `eval` would otherwise only discover mid-run.
+fn typecheck(expr: &Exp) -> Result<Ty, TypeError> {
+    let mut env = TyEnv::new();
+    let mut subst = Subst::default();
+    let ty = infer(expr, &mut env, &mut subst)?;
+    Ok(subst.resolve(&ty))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn ival(x: u64) -> Box<Exp> {
-        Box::new(Exp::Var(Val::Int(x)))
+        Box::new(Exp::Lit(Val::Int(x)))
     }
 
     fn fval(x: f64) -> Box<Exp> {
-        Box::new(Exp::Var(Val::Rat(x)))
+        Box::new(Exp::Lit(Val::Rat(x)))
+    }
+
+    fn eval_fresh(expr: Exp) -> Result<Val, EvalError> {
+        eval(expr, &mut Env::new())
     }
 
     #[test]
     fn test_eval() {
-        let exp1 = eval(Exp::Add(ival(10), fval(20.0)));
-        let exp2 = eval(Exp::Sub(ival(10), fval(20.0)));
-        let exp3 = eval(Exp::Mul(ival(10), fval(20.0)));
-        let exp4 = eval(Exp::Div(ival(10), fval(20.0)));
+        let exp1 = eval_fresh(Exp::Add(ival(10), fval(20.0)));
+        let exp2 = eval_fresh(Exp::Sub(ival(10), fval(20.0)));
+        let exp3 = eval_fresh(Exp::Mul(ival(10), fval(20.0)));
+        let exp4 = eval_fresh(Exp::Div(ival(10), fval(20.0)));
 
         // SAFETY: We know that this works!
         assert_eq!(exp1.unwrap(), Val::Rat(30.0));
@@ -107,6 +994,347 @@ mod tests {
         assert_eq!(exp3.unwrap(), Val::Rat(200.0));
         assert_eq!(exp4.unwrap(), Val::Rat(0.5));
     }
+
+    #[test]
+    fn test_eval_invalid_type() {
+        let exp = eval_fresh(Exp::Add(
+            ival(10),
+            Box::new(Exp::Lit(Val::Txt("nope".to_string()))),
+        ));
+
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::InvalidType {
+                expected: "Int or Rat",
+                got: Val::Txt("nope".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_let() {
+        let exp = eval_fresh(Exp::Let(
+            "x".to_string(),
+            ival(10),
+            Box::new(Exp::Add(Box::new(Exp::Var("x".to_string())), fval(5.0))),
+        ));
+
+        assert_eq!(exp.unwrap(), Val::Rat(15.0));
+    }
+
+    #[test]
+    fn test_eval_undefined_variable() {
+        let exp = eval_fresh(Exp::Var("x".to_string()));
+
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::UndefinedVariable("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eval_let_does_not_leak() {
+        let mut env = Env::new();
+        let exp = eval(
+            Exp::Let(
+                "x".to_string(),
+                ival(10),
+                Box::new(Exp::Add(
+                    Box::new(Exp::Var("x".to_string())),
+                    Box::new(Exp::Var("x".to_string())),
+                )),
+            ),
+            &mut env,
+        );
+        assert_eq!(exp.unwrap(), Val::Int(20));
+
+        let leaked = eval(Exp::Var("x".to_string()), &mut env);
+        assert_eq!(
+            leaked.unwrap_err(),
+            EvalError::UndefinedVariable("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eval_comparisons() {
+        assert_eq!(
+            eval_fresh(Exp::Lt(ival(1), fval(2.0))).unwrap(),
+            Val::Boo(true)
+        );
+        assert_eq!(
+            eval_fresh(Exp::Lte(ival(2), fval(2.0))).unwrap(),
+            Val::Boo(true)
+        );
+        assert_eq!(
+            eval_fresh(Exp::Gt(ival(3), fval(2.0))).unwrap(),
+            Val::Boo(true)
+        );
+        assert_eq!(
+            eval_fresh(Exp::Gte(ival(2), fval(2.0))).unwrap(),
+            Val::Boo(true)
+        );
+        assert_eq!(
+            eval_fresh(Exp::Eq(ival(2), fval(2.0))).unwrap(),
+            Val::Boo(true)
+        );
+        assert_eq!(
+            eval_fresh(Exp::Ne(ival(2), fval(3.0))).unwrap(),
+            Val::Boo(true)
+        );
+
+        let lhs = Box::new(Exp::Lit(Val::Txt("abc".to_string())));
+        let rhs = Box::new(Exp::Lit(Val::Txt("abd".to_string())));
+        assert_eq!(eval_fresh(Exp::Lt(lhs, rhs)).unwrap(), Val::Boo(true));
+    }
+
+    #[test]
+    fn test_eval_comparisons_blame_the_invalid_operand() {
+        // Nil isn't comparable at all; the error should name it, not the
+        // other (perfectly comparable) operand.
+        let exp = eval_fresh(Exp::Lt(
+            Box::new(Exp::Lit(Val::Nil)),
+            Box::new(Exp::Lit(Val::Int(1))),
+        ));
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::InvalidType {
+                expected: "comparable operands (Int/Rat, Txt, or Boo)",
+                got: Val::Nil,
+            }
+        );
+
+        let exp = eval_fresh(Exp::Lt(
+            Box::new(Exp::Lit(Val::Int(1))),
+            Box::new(Exp::Lit(Val::Nil)),
+        ));
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::InvalidType {
+                expected: "comparable operands (Int/Rat, Txt, or Boo)",
+                got: Val::Nil,
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_comparisons_incomparable_types() {
+        // Int and Txt are each individually comparable, but not to each
+        // other; neither side is the "invalid" one, so both are named.
+        let exp = eval_fresh(Exp::Lt(
+            Box::new(Exp::Lit(Val::Int(1))),
+            Box::new(Exp::Lit(Val::Txt("x".to_string()))),
+        ));
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::IncomparableTypes {
+                left: Val::Int(1),
+                right: Val::Txt("x".to_string()),
+            }
+        );
+
+        let exp = eval_fresh(Exp::Lt(
+            Box::new(Exp::Lit(Val::Boo(true))),
+            Box::new(Exp::Lit(Val::Int(1))),
+        ));
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::IncomparableTypes {
+                left: Val::Boo(true),
+                right: Val::Int(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_booleans() {
+        let t = || Box::new(Exp::Lit(Val::Boo(true)));
+        let f = || Box::new(Exp::Lit(Val::Boo(false)));
+
+        assert_eq!(eval_fresh(Exp::And(t(), f())).unwrap(), Val::Boo(false));
+        assert_eq!(eval_fresh(Exp::Or(t(), f())).unwrap(), Val::Boo(true));
+        assert_eq!(eval_fresh(Exp::Not(t())).unwrap(), Val::Boo(false));
+
+        assert_eq!(
+            eval_fresh(Exp::Not(ival(1))).unwrap_err(),
+            EvalError::InvalidType {
+                expected: "Boo",
+                got: Val::Int(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_if() {
+        let cond = Box::new(Exp::Lit(Val::Boo(true)));
+        let exp = eval_fresh(Exp::If(cond, ival(1), ival(2)));
+        assert_eq!(exp.unwrap(), Val::Int(1));
+
+        let cond = Box::new(Exp::Lit(Val::Boo(false)));
+        let exp = eval_fresh(Exp::If(cond, ival(1), ival(2)));
+        assert_eq!(exp.unwrap(), Val::Int(2));
+    }
+
+    #[test]
+    fn test_eval_if_is_lazy() {
+        // The untaken branch divides by zero; it must never be evaluated.
+        let cond = Box::new(Exp::Lit(Val::Boo(true)));
+        let guarded_div = Box::new(Exp::Div(ival(1), ival(0)));
+        let exp = eval_fresh(Exp::If(cond, ival(42), guarded_div));
+        assert_eq!(exp.unwrap(), Val::Int(42));
+    }
+
+    #[test]
+    fn test_eval_if_non_boolean_condition() {
+        let exp = eval_fresh(Exp::If(ival(1), ival(1), ival(2)));
+        assert_eq!(
+            exp.unwrap_err(),
+            EvalError::InvalidType {
+                expected: "Boo",
+                got: Val::Int(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_int_divide_by_zero() {
+        let exp = eval_fresh(Exp::Div(ival(1), ival(0)));
+        assert_eq!(exp.unwrap_err(), EvalError::DivideByZero);
+    }
+
+    #[test]
+    fn test_eval_int_underflow() {
+        let exp = eval_fresh(Exp::Sub(ival(0), ival(1)));
+        assert_eq!(exp.unwrap_err(), EvalError::ArithmeticOverflow);
+    }
+
+    #[test]
+    fn test_eval_int_overflow() {
+        let exp = eval_fresh(Exp::Add(ival(u64::MAX), ival(1)));
+        assert_eq!(exp.unwrap_err(), EvalError::ArithmeticOverflow);
+    }
+
+    #[test]
+    fn test_eval_rat_divide_by_zero() {
+        let exp = eval_fresh(Exp::Div(fval(1.0), fval(0.0)));
+        assert_eq!(exp.unwrap_err(), EvalError::DivideByZero);
+    }
+
+    fn parse_and_eval(source: &str) -> Result<Val, Box<dyn std::error::Error>> {
+        let expr = parse(source)?;
+        Ok(eval(expr, &mut Env::new())?)
+    }
+
+    #[test]
+    fn test_parse_arithmetic_precedence() {
+        assert_eq!(parse_and_eval("2 + 3 * 4").unwrap(), Val::Int(14));
+        assert_eq!(parse_and_eval("(2 + 3) * 4").unwrap(), Val::Int(20));
+        assert_eq!(parse_and_eval("10 - 2 - 3").unwrap(), Val::Int(5));
+        assert_eq!(parse_and_eval("2.5 + 1").unwrap(), Val::Rat(3.5));
+    }
+
+    #[test]
+    fn test_parse_let_and_if() {
+        assert_eq!(parse_and_eval("let x = 10 in x + 5").unwrap(), Val::Int(15));
+        assert_eq!(
+            parse_and_eval("if 1 < 2 then \"yes\" else \"no\"").unwrap(),
+            Val::Txt("yes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_booleans_and_comparisons() {
+        assert_eq!(parse_and_eval("not true").unwrap(), Val::Boo(false));
+        assert_eq!(parse_and_eval("true and false").unwrap(), Val::Boo(false));
+        assert_eq!(parse_and_eval("true or false").unwrap(), Val::Boo(true));
+        assert_eq!(parse_and_eval("1 <= 2").unwrap(), Val::Boo(true));
+        assert_eq!(parse_and_eval("\"a\" != \"b\"").unwrap(), Val::Boo(true));
+    }
+
+    #[test]
+    fn test_parse_reports_position_on_error() {
+        let err = parse("1 +").unwrap_err();
+        assert_eq!(err.position, 3);
+    }
+
+    #[test]
+    fn test_parse_unterminated_string() {
+        let err = parse("\"abc").unwrap_err();
+        assert_eq!(err.position, 0);
+    }
+
+    #[test]
+    fn test_typecheck_arithmetic_promotes_to_rat() {
+        assert_eq!(typecheck(&parse("1 + 2").unwrap()).unwrap(), Ty::Int);
+        assert_eq!(typecheck(&parse("1 + 2.0").unwrap()).unwrap(), Ty::Rat);
+    }
+
+    #[test]
+    fn test_typecheck_rejects_mismatched_arithmetic() {
+        let expr = Exp::Add(ival(1), Box::new(Exp::Lit(Val::Txt("nope".to_string()))));
+        assert_eq!(
+            typecheck(&expr).unwrap_err(),
+            TypeError::Mismatch {
+                expected: Ty::Int,
+                got: Ty::Txt,
+            }
+        );
+    }
+
+    #[test]
+    fn test_typecheck_comparison_promotes_mixed_numeric() {
+        assert_eq!(typecheck(&parse("1 < 2.0").unwrap()).unwrap(), Ty::Bool);
+    }
+
+    #[test]
+    fn test_typecheck_let_and_var() {
+        let ty = typecheck(&parse("let x = 10 in x + 1").unwrap()).unwrap();
+        assert_eq!(ty, Ty::Int);
+    }
+
+    #[test]
+    fn test_typecheck_undefined_variable() {
+        let err = typecheck(&parse("x + 1").unwrap()).unwrap_err();
+        assert_eq!(err, TypeError::UndefinedVariable("x".to_string()));
+    }
+
+    #[test]
+    fn test_typecheck_if_unifies_branches() {
+        let ty = typecheck(&parse("if true then 1 else 2").unwrap()).unwrap();
+        assert_eq!(ty, Ty::Int);
+
+        let err = typecheck(&parse("if true then 1 else \"two\"").unwrap()).unwrap_err();
+        assert_eq!(
+            err,
+            TypeError::Mismatch {
+                expected: Ty::Int,
+                got: Ty::Txt,
+            }
+        );
+    }
+
+    #[test]
+    fn test_typecheck_if_promotes_mixed_numeric_branches() {
+        let ty = typecheck(&parse("if true then 1 else 2.0").unwrap()).unwrap();
+        assert_eq!(ty, Ty::Rat);
+    }
+
+    #[test]
+    fn test_typecheck_boolean_operators() {
+        assert_eq!(
+            typecheck(&parse("true and false").unwrap()).unwrap(),
+            Ty::Bool
+        );
+        assert_eq!(typecheck(&parse("1 < 2").unwrap()).unwrap(), Ty::Bool);
+
+        let err = typecheck(&parse("not 1").unwrap()).unwrap_err();
+        assert_eq!(
+            err,
+            TypeError::Mismatch {
+                expected: Ty::Bool,
+                got: Ty::Int,
+            }
+        );
+    }
 }
 
 fn main() {